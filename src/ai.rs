@@ -0,0 +1,275 @@
+//! Self-playing paddle: a small feed-forward network maps ball state to a
+//! paddle move, and a population of networks is evolved with a genetic
+//! algorithm (selection, crossover, Gaussian mutation) against the same
+//! headless `Game::step` the human-playable path runs on.
+
+use rand::prelude::*;
+use raylib::prelude::*;
+
+use crate::{Game, DT, H, W};
+
+const INPUT_SIZE: usize = 5;
+const HIDDEN_SIZE: usize = 8;
+
+const POPULATION_SIZE: usize = 30;
+const ELITE_COUNT: usize = 4;
+const MUT_RATE: f32 = 0.2;
+
+// normalizes ball velocity components into roughly [-1, 1]; balls spawn
+// with speeds up to 600 units/sec (see `spawn_balls`).
+const MAX_BALL_SPEED: f32 = 900.0;
+const MOVE_THRESHOLD: f32 = 0.33;
+const PAD_SPEED: f32 = 20.0;
+
+// each genome gets this much sim time to clear the board; if it clears
+// the board early the sim stops there, so faster clears mean less elapsed
+// time for the same destruction score.
+const SIM_SECONDS: f32 = 20.0;
+const SHARD_FITNESS_WEIGHT: f32 = 20.0;
+
+/// A single hidden-layer feed-forward network: `tanh` hidden units and a
+/// single linear-ish output, read as a left/stay/right paddle move.
+#[derive(Clone)]
+pub struct NN {
+    w1: Vec<Vec<f32>>, // HIDDEN_SIZE x INPUT_SIZE
+    b1: Vec<f32>,
+    w2: Vec<f32>, // single output neuron, HIDDEN_SIZE weights
+    b2: f32,
+}
+
+impl NN {
+    fn random(rng: &mut impl Rng) -> Self {
+        NN {
+            w1: (0..HIDDEN_SIZE)
+                .map(|_| (0..INPUT_SIZE).map(|_| gaussian(rng)).collect())
+                .collect(),
+            b1: (0..HIDDEN_SIZE).map(|_| gaussian(rng)).collect(),
+            w2: (0..HIDDEN_SIZE).map(|_| gaussian(rng)).collect(),
+            b2: gaussian(rng),
+        }
+    }
+
+    fn feed_forward(&self, input: &[f32; INPUT_SIZE]) -> f32 {
+        let hidden: Vec<f32> = self
+            .w1
+            .iter()
+            .zip(&self.b1)
+            .map(|(weights, bias)| {
+                let sum: f32 = weights.iter().zip(input).map(|(w, x)| w * x).sum();
+                (sum + bias).tanh()
+            })
+            .collect();
+
+        let out: f32 = self.w2.iter().zip(&hidden).map(|(w, h)| w * h).sum::<f32>() + self.b2;
+        out.tanh()
+    }
+
+    fn crossover(a: &NN, b: &NN, rng: &mut impl Rng) -> NN {
+        NN {
+            w1: a
+                .w1
+                .iter()
+                .zip(&b.w1)
+                .map(|(x, y)| crossover_vec(x, y, rng))
+                .collect(),
+            b1: crossover_vec(&a.b1, &b.b1, rng),
+            w2: crossover_vec(&a.w2, &b.w2, rng),
+            b2: if rng.random_bool(0.5) { a.b2 } else { b.b2 },
+        }
+    }
+
+    fn mutate(&mut self, rng: &mut impl Rng) {
+        for row in &mut self.w1 {
+            mutate_vec(row, rng);
+        }
+        mutate_vec(&mut self.b1, rng);
+        mutate_vec(&mut self.w2, rng);
+        self.b2 += gaussian(rng) * MUT_RATE;
+    }
+}
+
+// Box-Muller transform: turns two uniform samples into one standard-normal
+// sample, so weight initialization and mutation don't need a stats crate.
+fn gaussian(rng: &mut impl Rng) -> f32 {
+    let u1: f32 = rng.random_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+}
+
+fn crossover_vec(a: &[f32], b: &[f32], rng: &mut impl Rng) -> Vec<f32> {
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| if rng.random_bool(0.5) { x } else { y })
+        .collect()
+}
+
+fn mutate_vec(v: &mut [f32], rng: &mut impl Rng) {
+    for x in v {
+        *x += gaussian(rng) * MUT_RATE;
+    }
+}
+
+/// Reads the given network's move for the current state of `game` (the
+/// first ball's position/velocity plus the pad's position) into a
+/// horizontal delta, same units `Pad::translate` expects from a human.
+pub fn drive_paddle(nn: &NN, game: &Game) -> f32 {
+    let Some(ball) = game.balls.first() else {
+        return 0.0;
+    };
+    let input = [
+        ball.pos.x / W as f32,
+        ball.pos.y / H as f32,
+        ball.speed.x / MAX_BALL_SPEED,
+        ball.speed.y / MAX_BALL_SPEED,
+        game.pad.poly[0].x / W as f32,
+    ];
+    let out = nn.feed_forward(&input);
+    if out > MOVE_THRESHOLD {
+        PAD_SPEED
+    } else if out < -MOVE_THRESHOLD {
+        -PAD_SPEED
+    } else {
+        0.0
+    }
+}
+
+/// One genome's headless playthrough: its own game instance driven only
+/// by its own network, with no rendering and no human input.
+struct Sim {
+    game: Game,
+    shards_destroyed: u32,
+    elapsed: f32,
+}
+
+impl Sim {
+    fn any_shards_left(&self) -> bool {
+        self.game
+            .shards
+            .iter()
+            .any(|s| s.hp >= 1 && s.debris.is_none())
+    }
+
+    fn finished(&self) -> bool {
+        self.elapsed >= SIM_SECONDS || !self.any_shards_left() || self.game.balls.is_empty()
+    }
+
+    // `elapsed` is how long the genome kept at least one ball alive -- a
+    // genome that lets every ball fall stops early and scores less of it,
+    // so survival (paddle play) and shard-clearing both pull on fitness.
+    fn fitness(&self) -> f32 {
+        self.elapsed + self.shards_destroyed as f32 * SHARD_FITNESS_WEIGHT
+    }
+}
+
+/// How many fixed `DT` steps `Population::tick` advances each still-running
+/// sim by, per call. Keeps any one call cheap enough to run once per
+/// rendered frame without stalling it, while still making visible progress
+/// generation over generation.
+const STEPS_PER_TICK: u32 = 40;
+
+/// A generation's in-flight headless evaluation: every genome's `Sim`,
+/// advanced a little further each time `Population::tick` is called.
+struct Training {
+    sims: Vec<Sim>,
+}
+
+/// A population of `NN` genomes evolved generation over generation by a
+/// genetic algorithm: each genome plays its own headless game, the
+/// fittest are kept as elites, and the rest of the next generation is bred
+/// from them by crossover plus Gaussian mutation.
+pub struct Population {
+    genomes: Vec<NN>,
+    pub generation: usize,
+    training: Option<Training>,
+}
+
+impl Population {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        Population {
+            genomes: (0..POPULATION_SIZE).map(|_| NN::random(rng)).collect(),
+            generation: 0,
+            training: None,
+        }
+    }
+
+    pub fn is_training(&self) -> bool {
+        self.training.is_some()
+    }
+
+    /// Starts headless evaluation of the current generation. Every genome
+    /// plays a clone of `template`, so they all face the identical board
+    /// and ball spawn -- fitness is then comparable across genomes instead
+    /// of being decided by who happened to draw the easier layout.
+    pub fn start_generation(&mut self, template: &Game) {
+        self.training = Some(Training {
+            sims: self
+                .genomes
+                .iter()
+                .map(|_| Sim {
+                    game: template.clone(),
+                    shards_destroyed: 0,
+                    elapsed: 0.0,
+                })
+                .collect(),
+        });
+    }
+
+    /// Advances the in-progress generation by up to `STEPS_PER_TICK` fixed
+    /// steps per genome, run across OS threads so the population's sims
+    /// don't serialize onto one core. Intended to be called once per
+    /// rendered frame (via `start_generation` first); returns the best
+    /// genome and its fitness once every sim has cleared the board, lost
+    /// every ball or timed out and the next generation has been bred, `None`
+    /// while still in
+    /// progress so the caller can keep rendering in the meantime.
+    pub fn tick(&mut self, rng: &mut impl Rng) -> Option<(NN, f32)> {
+        let genomes = &self.genomes;
+        let training = self.training.as_mut()?;
+
+        std::thread::scope(|scope| {
+            for (genome, sim) in genomes.iter().zip(&mut training.sims) {
+                scope.spawn(move || {
+                    for _ in 0..STEPS_PER_TICK {
+                        if sim.finished() {
+                            break;
+                        }
+                        let delta = drive_paddle(genome, &sim.game);
+                        sim.game.pad.translate(&Vector2::new(delta, 0.0));
+                        sim.shards_destroyed += sim.game.step(DT);
+                        sim.elapsed += DT;
+                    }
+                });
+            }
+        });
+
+        if !training.sims.iter().all(Sim::finished) {
+            return None;
+        }
+
+        let training = self.training.take().expect("checked Some above");
+        let mut scored: Vec<(NN, f32)> = self
+            .genomes
+            .drain(..)
+            .zip(&training.sims)
+            .map(|(nn, sim)| (nn, sim.fitness()))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let best = scored[0].0.clone();
+        let best_fitness = scored[0].1;
+
+        let elites: Vec<&NN> = scored.iter().take(ELITE_COUNT).map(|(nn, _)| nn).collect();
+        let mut next_gen: Vec<NN> = elites.iter().map(|nn| (**nn).clone()).collect();
+        while next_gen.len() < POPULATION_SIZE {
+            let a = elites.choose(rng).expect("elites is never empty");
+            let b = elites.choose(rng).expect("elites is never empty");
+            let mut child = NN::crossover(a, b, rng);
+            child.mutate(rng);
+            next_gen.push(child);
+        }
+
+        self.genomes = next_gen;
+        self.generation += 1;
+        Some((best, best_fitness))
+    }
+}
@@ -1,38 +1,97 @@
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use std::{f32, time::Instant};
 
 // use delaunator::{Point, triangulate};
 use raylib::prelude::*;
 use voronoice::{BoundingBox, Point, VoronoiBuilder};
 
+mod ai;
+
 const W: i32 = 1024;
 const H: i32 = 768;
 const PAD_W: i32 = 60;
 const PAD_H: i32 = 10;
 const BALL_R: f32 = 10.0;
+// how much +/- changes the coefficient of restitution (`Game::c_r`) for
+// ball-ball collisions: 1.0 is perfectly elastic, 0.0 is perfectly inelastic.
+const C_R_STEP: f32 = 0.05;
+
+// fixed-timestep simulation: update() is driven by wall-clock time but the
+// physics itself always advances in DT-sized (1/UPDATE_RATE s) slices, so behavior
+// doesn't depend on the render frame rate.
+const UPDATE_RATE: u32 = 120;
+const DT: f32 = 1.0 / UPDATE_RATE as f32;
+// if the accumulator ever needs more sub-steps than this (e.g. after a
+// debugger pause or a big stall) we drop the rest rather than spiral.
+const MAX_SUBSTEPS: u32 = 10;
+
+// how long a fragment produced by shattering a shard drifts and fades
+// before it's removed, and how fast it drifts outward from the parent.
+const DEBRIS_LIFETIME: f32 = 0.6;
+const DEBRIS_SPEED: f32 = 80.0;
+// number of Voronoi sites scattered inside a shard when it shatters.
+const FRACTURE_SITES: std::ops::RangeInclusive<u32> = 4..=8;
+// a ball's per-step movement is sampled in sub-steps no larger than its
+// radius (capped at this many) so it can't tunnel through a thin shard.
+const MAX_SWEEP_STEPS: u32 = 8;
+
+// gamepad input for the paddle.
+const GAMEPAD_ID: i32 = 0;
+const GAMEPAD_DEADZONE: f32 = 0.15;
+const GAMEPAD_PAD_SPEED: f32 = 20.0;
+
+/// A single point of contact between a ball and a polygon: the surface
+/// normal to reflect off of, the closest point on the polygon, and how
+/// deep the ball has already sunk in, so the caller can push it back out.
+#[derive(Clone, Copy)]
+struct Contact {
+    normal: Vector2,
+    point: Vector2,
+    penetration: f32,
+}
+
+#[derive(Clone)]
+struct Debris {
+    velocity: Vector2,
+    age: f32,
+}
 
+#[derive(Clone)]
 struct Shard {
     edges: Vec<Vector2>,
     center: Vector2,
     hp: i32,
     id: usize,
+    // set once a shard is itself a fragment of a shattered shard: it drifts
+    // and fades instead of taking further hits.
+    debris: Option<Debris>,
 }
 
+#[derive(Clone)]
 struct Pad {
     poly: Vec<Vector2>,
 }
 
+#[derive(Clone)]
 struct Ball {
     pos: Vector2,
     radius: f32,
     speed: Vector2,
 }
 
+// cloneable so the `ai` subsystem can give every genome in a generation its
+// own copy of the exact same board instead of rolling a fresh one each.
+#[derive(Clone)]
 struct Game {
     pad: Pad,
     shards: Vec<Shard>,
-    ball: Ball,
+    balls: Vec<Ball>,
     last_update: Instant,
+    accumulator: f32,
+    c_r: f32,
+    rng: StdRng,
+    next_shard_id: usize,
 }
 
 impl Shard {
@@ -43,7 +102,14 @@ impl Shard {
             3 => Color::ORANGE,
             _ => Color::DARKORANGE,
         };
+        let color = match &self.debris {
+            Some(d) => color.fade(1.0 - d.age / DEBRIS_LIFETIME),
+            None => color,
+        };
 
+        if self.edges.len() < 3 {
+            return;
+        }
         dh.draw_triangle_fan(&self.edges, color);
         for i in 0..self.edges.len() {
             // outline
@@ -54,6 +120,156 @@ impl Shard {
             );
         }
     }
+
+    /// Shatters this shard into several smaller shards by scattering a few
+    /// sites inside its bounding box, building a local Voronoi diagram, and
+    /// clipping each resulting cell against this shard's own polygon so
+    /// fragments never spill outside the parent's shape. The fragments are
+    /// debris: they drift away from `hit_point` (where the ball actually
+    /// struck the shard) and fade out over time.
+    fn shatter(&self, hit_point: Vector2, rng: &mut impl Rng, next_id: &mut usize) -> Vec<Shard> {
+        let (min, max) = polygon_bbox(&self.edges);
+        let w = (max.x - min.x) as f64;
+        let h = (max.y - min.y) as f64;
+        if w <= 0.0 || h <= 0.0 {
+            return Vec::new();
+        }
+
+        let n_sites = rng.random_range(FRACTURE_SITES);
+        let sites: Vec<Point> = (0..n_sites)
+            .map(|_| Point {
+                x: rng.random_range(min.x as f64..max.x as f64),
+                y: rng.random_range(min.y as f64..max.y as f64),
+            })
+            .collect();
+
+        let Ok(voronoi) = VoronoiBuilder::default()
+            .set_sites(sites)
+            .set_bounding_box(BoundingBox::new(
+                Point {
+                    x: (min.x + max.x) as f64 / 2.0,
+                    y: (min.y + max.y) as f64 / 2.0,
+                },
+                w,
+                h,
+            ))
+            .build()
+        else {
+            // degenerate shard (too small/thin to scatter sites in):
+            // fall back to the old behavior of just disappearing.
+            return Vec::new();
+        };
+
+        voronoi
+            .iter_cells()
+            .filter_map(|c| {
+                let cell: Vec<Vector2> = c
+                    .iter_vertices()
+                    .map(|v| Vector2::new(v.x as f32, v.y as f32))
+                    .collect();
+                let edges = clip_polygon(&cell, &self.edges);
+                if edges.len() < 3 {
+                    return None;
+                }
+                let center = polygon_centroid(&edges);
+                let id = *next_id;
+                *next_id += 1;
+                Some(Shard {
+                    debris: Some(Debris {
+                        velocity: (center - hit_point).normalized() * DEBRIS_SPEED,
+                        age: 0.0,
+                    }),
+                    edges,
+                    center,
+                    hp: 1,
+                    id,
+                })
+            })
+            .collect()
+    }
+}
+
+fn polygon_bbox(poly: &[Vector2]) -> (Vector2, Vector2) {
+    let mut min = poly[0];
+    let mut max = poly[0];
+    for p in poly {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    (min, max)
+}
+
+fn polygon_centroid(poly: &[Vector2]) -> Vector2 {
+    let sum = poly.iter().fold(Vector2::default(), |acc, &p| acc + p);
+    sum * (1.0 / poly.len() as f32)
+}
+
+/// Clips convex polygon `subject` against convex polygon `clip` using the
+/// Sutherland-Hodgman algorithm. Works regardless of either polygon's
+/// winding order.
+fn clip_polygon(subject: &[Vector2], clip: &[Vector2]) -> Vec<Vector2> {
+    if clip.len() < 3 {
+        return subject.to_vec();
+    }
+    let ccw = polygon_signed_area(clip) >= 0.0;
+
+    let mut output = subject.to_vec();
+    for i in 0..clip.len() {
+        if output.is_empty() {
+            break;
+        }
+        let a = clip[i];
+        let b = clip[(i + 1) % clip.len()];
+        let input = output;
+        output = Vec::with_capacity(input.len() + 1);
+        for j in 0..input.len() {
+            let cur = input[j];
+            let prev = input[(j + input.len() - 1) % input.len()];
+            let cur_inside = is_inside(cur, a, b, ccw);
+            let prev_inside = is_inside(prev, a, b, ccw);
+            if cur_inside {
+                if !prev_inside {
+                    output.push(segment_intersection(prev, cur, a, b));
+                }
+                output.push(cur);
+            } else if prev_inside {
+                output.push(segment_intersection(prev, cur, a, b));
+            }
+        }
+    }
+    output
+}
+
+fn polygon_signed_area(poly: &[Vector2]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..poly.len() {
+        let a = poly[i];
+        let b = poly[(i + 1) % poly.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    area * 0.5
+}
+
+fn is_inside(p: Vector2, a: Vector2, b: Vector2, ccw: bool) -> bool {
+    let cross = (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+    if ccw {
+        cross >= 0.0
+    } else {
+        cross <= 0.0
+    }
+}
+
+fn segment_intersection(p1: Vector2, p2: Vector2, p3: Vector2, p4: Vector2) -> Vector2 {
+    let d1 = p2 - p1;
+    let d2 = p4 - p3;
+    let denom = d1.x * d2.y - d1.y * d2.x;
+    if denom.abs() < f32::EPSILON {
+        return p2;
+    }
+    let t = ((p3.x - p1.x) * d2.y - (p3.y - p1.y) * d2.x) / denom;
+    p1 + d1 * t
 }
 
 impl Pad {
@@ -84,14 +300,48 @@ impl Pad {
     }
 }
 
+/// Combines mouse and gamepad input into the single horizontal delta the
+/// paddle moves by each frame, so `Pad::translate` doesn't need to know
+/// where the input came from. Prefers the gamepad's left stick when one
+/// is connected and off its dead-zone, and falls back to the mouse.
+struct PaddleInput;
+
+impl PaddleInput {
+    fn delta(rl: &RaylibHandle) -> f32 {
+        if rl.is_gamepad_available(GAMEPAD_ID) {
+            let x = rl.get_gamepad_axis_movement(GAMEPAD_ID, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
+            let x = x.clamp(-1.0, 1.0);
+            if x.abs() > GAMEPAD_DEADZONE {
+                return x * GAMEPAD_PAD_SPEED;
+            }
+        }
+        rl.get_mouse_delta().x / 2.0
+    }
+
+    /// Whether the "launch/reset the ball" action was pressed this frame,
+    /// on whichever input source is active.
+    fn launch_pressed(rl: &RaylibHandle) -> bool {
+        if rl.is_gamepad_available(GAMEPAD_ID)
+            && rl.is_gamepad_button_pressed(
+                GAMEPAD_ID,
+                GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+            )
+        {
+            return true;
+        }
+        rl.is_key_pressed(KeyboardKey::KEY_SPACE)
+    }
+}
+
 impl Ball {
-    fn collides(&self, poly: &[Vector2]) -> Option<Vector2> {
+    fn collides(&self, poly: &[Vector2]) -> Option<Contact> {
         if poly.len() < 2 {
             return None;
         }
 
         let mut best_pen = -f32::INFINITY;
         let mut best_n = Vector2::default();
+        let mut best_point = Vector2::default();
         for i in 0..poly.len() {
             let a = poly[i];
             let b = poly[(i + 1) % poly.len()];
@@ -107,6 +357,7 @@ impl Ball {
                 continue;
             }
             best_pen = pen;
+            best_point = cp;
             best_n = if dist > 0.0 {
                 delta * (1.0 / dist)
             } else {
@@ -116,7 +367,11 @@ impl Ball {
         }
 
         if best_pen > 0.0 {
-            Some(best_n.normalized())
+            Some(Contact {
+                normal: best_n.normalized(),
+                point: best_point,
+                penetration: best_pen,
+            })
         } else {
             None
         }
@@ -127,9 +382,122 @@ impl Ball {
         let t = (self.pos - a).dot(d) / d.dot(d);
         a + d * t.clamp(0.0, 1.0)
     }
+
+    // mass proportional to area, used only to weight ball-ball impulses.
+    fn mass(&self) -> f32 {
+        self.radius * self.radius
+    }
+}
+
+/// Resolves an elastic/inelastic collision between two balls in place,
+/// given a coefficient of restitution `c_r` in `[0, 1]`.
+fn resolve_ball_collision(a: &mut Ball, b: &mut Ball, c_r: f32) {
+    let delta = b.pos - a.pos;
+    let dist = delta.length();
+    let min_dist = a.radius + b.radius;
+    if dist >= min_dist {
+        return;
+    }
+
+    let n = if dist > 0.0 {
+        delta * (1.0 / dist)
+    } else {
+        Vector2::new(1.0, 0.0)
+    };
+
+    let v_rel = b.speed - a.speed;
+    let vn = v_rel.dot(n);
+    if vn >= 0.0 {
+        // already separating
+        return;
+    }
+
+    let m_a = a.mass();
+    let m_b = b.mass();
+    let j = -(1.0 + c_r) * vn / (1.0 / m_a + 1.0 / m_b);
+    a.speed -= n * (j / m_a);
+    b.speed += n * (j / m_b);
+
+    // positional correction so the balls don't keep sticking together
+    let penetration = min_dist - dist;
+    let correction = n * (penetration * 0.5);
+    a.pos -= correction;
+    b.pos += correction;
 }
 
 impl Game {
+    /// Builds a fresh game: a field of Voronoi shards, a centered pad, and
+    /// the starting balls. Used both for the human-playable game and for
+    /// the template board that each `ai` generation's headless games are
+    /// cloned from.
+    fn new(mut rng: StdRng) -> Self {
+        let mut points = Vec::with_capacity(100);
+        for _ in 0..points.capacity() {
+            points.push(Point {
+                x: rng.random_range(0.0..W as f64),
+                y: rng.random_range(0.0..(H / 3) as f64),
+            });
+        }
+        let voronoi = VoronoiBuilder::default()
+            .set_sites(points)
+            .set_bounding_box(BoundingBox::new(
+                Point {
+                    x: (W / 2) as f64,
+                    y: (H / 6) as f64,
+                },
+                W as f64,
+                (H / 3) as f64,
+            ))
+            .set_lloyd_relaxation_iterations(5)
+            .build()
+            .expect("building shards");
+        let shards: Vec<_> = voronoi
+            .iter_cells()
+            .enumerate()
+            .map(|(i, c)| Shard {
+                center: Vector2 {
+                    x: c.site_position().x as f32,
+                    y: c.site_position().y as f32,
+                },
+                edges: c
+                    .iter_vertices()
+                    .map(|v| Vector2::new(v.x as f32, v.y as f32))
+                    .collect(),
+                hp: rng.random_range(1..5),
+                id: i,
+                debris: None,
+            })
+            .collect();
+        let next_shard_id = shards.len();
+        let balls = spawn_balls(&mut rng);
+
+        Game {
+            last_update: Instant::now(),
+            accumulator: 0.0,
+            next_shard_id,
+            pad: Pad {
+                // vertexes shall go counter-clockwise:
+                //  3 +--------+ 2
+                //    |        |
+                //  0 +--------+ 1
+                poly: vec![
+                    Vector2::new((W / 2) as f32, (H) as f32),
+                    Vector2::new((W / 2 + PAD_W) as f32, (H) as f32),
+                    Vector2::new((W / 2 + PAD_W) as f32, (H - PAD_H) as f32),
+                    Vector2::new((W / 2) as f32, (H - PAD_H) as f32),
+                ],
+            },
+            shards,
+            balls,
+            c_r: 1.0,
+            rng,
+        }
+    }
+
+    fn reset_balls(&mut self) {
+        self.balls = spawn_balls(&mut self.rng);
+    }
+
     fn render(&self, rl: &mut RaylibHandle, thread: &RaylibThread) {
         let mut d = rl.begin_drawing(thread);
         d.clear_background(Color::BLACK);
@@ -153,44 +521,182 @@ impl Game {
             );
         }
 
-        d.draw_circle(
-            self.ball.pos.x as i32,
-            self.ball.pos.y as i32,
-            self.ball.radius,
-            Color::LIGHTBLUE,
+        for b in &self.balls {
+            d.draw_circle(b.pos.x as i32, b.pos.y as i32, b.radius, Color::LIGHTBLUE);
+        }
+
+        d.draw_text(
+            &format!("C_r: {:.2}", self.c_r),
+            10,
+            10,
+            20,
+            Color::LIGHTGRAY,
         );
     }
 
     fn update(&mut self, rl: &RaylibHandle) {
-        self.last_update = Instant::now();
+        // PaddleInput gives one delta for the whole rendered frame; split
+        // it evenly across this frame's fixed sub-steps so the paddle ends
+        // up moving by the same total amount regardless of how many
+        // sub-steps (one or more) the accumulator happens to run. A frame
+        // that runs no sub-step does not move the paddle at all.
+        let frame_delta = PaddleInput::delta(rl);
+        self.update_with_pad_source(rl, move |_, substeps| frame_delta / substeps as f32);
+    }
+
+    /// Like `update`, but the paddle is driven by `nn` instead of
+    /// `PaddleInput`. The network is re-evaluated against the live game
+    /// state every fixed sub-step, exactly as it is during `Population`
+    /// training, so a genome plays live with the same control authority it
+    /// was scored under.
+    fn update_ai(&mut self, rl: &RaylibHandle, nn: &ai::NN) {
+        self.update_with_pad_source(rl, |game, _substeps| ai::drive_paddle(nn, game));
+    }
+
+    /// Shared update body: `pad_delta` is called once per fixed sub-step
+    /// (passed the total sub-step count this frame will run) so every
+    /// control source -- human or AI -- sees the game state it's actually
+    /// about to act on.
+    fn update_with_pad_source(
+        &mut self,
+        rl: &RaylibHandle,
+        mut pad_delta: impl FnMut(&Self, u32) -> f32,
+    ) {
+        let now = Instant::now();
+        let elapsed = (now - self.last_update).as_secs_f32();
+        self.last_update = now;
 
-        // moving pad
-        self.pad
-            .translate(&Vector2::new(rl.get_mouse_delta().x / 2.0, 0.0));
+        if PaddleInput::launch_pressed(rl) {
+            self.reset_balls();
+        }
 
-        // ball collisions
-        self.ball.pos += self.ball.speed;
-        if self.ball.pos.x <= self.ball.radius || self.ball.pos.x >= W as f32 - self.ball.radius {
-            self.ball.speed.x *= -1.0;
+        if rl.is_key_pressed(KeyboardKey::KEY_EQUAL) || rl.is_key_pressed(KeyboardKey::KEY_KP_ADD) {
+            self.c_r = (self.c_r + C_R_STEP).min(1.0);
         }
-        if self.ball.pos.y <= self.ball.radius || self.ball.pos.y >= H as f32 - self.ball.radius {
-            self.ball.speed.y *= -1.0;
+        if rl.is_key_pressed(KeyboardKey::KEY_MINUS)
+            || rl.is_key_pressed(KeyboardKey::KEY_KP_SUBTRACT)
+        {
+            self.c_r = (self.c_r - C_R_STEP).max(0.0);
         }
 
-        if let Some(n) = self.ball.collides(&self.pad.poly) {
-            self.ball.speed = reflect(self.ball.speed, n);
+        self.accumulator += elapsed;
+        let total_substeps = ((self.accumulator / DT).floor() as u32)
+            .min(MAX_SUBSTEPS)
+            .max(1);
+
+        let mut substeps = 0;
+        while self.accumulator >= DT {
+            let delta = pad_delta(self, total_substeps);
+            self.pad.translate(&Vector2::new(delta, 0.0));
+            self.step(DT);
+            self.accumulator -= DT;
+
+            substeps += 1;
+            if substeps >= MAX_SUBSTEPS {
+                // avoid a spiral of death after a stall; drop the rest of
+                // the backlog instead of trying to catch up all at once.
+                self.accumulator = 0.0;
+                break;
+            }
         }
+    }
 
-        for s in &mut self.shards {
-            if s.hp < 1 {
-                continue;
+    /// Advances the simulation by exactly `dt` seconds, returning how many
+    /// shards were destroyed (and shattered into debris) this step.
+    fn step(&mut self, dt: f32) -> u32 {
+        let mut shards_destroyed = 0;
+        for ball in &mut self.balls {
+            // swept movement: sample the path in sub-steps no bigger than
+            // the ball's own radius, and stop at the first contact, so a
+            // fast ball can't skip clean through a thin shard between two
+            // positions we'd otherwise only test at the end of the step.
+            let travel = ball.speed * dt;
+            let substeps = ((travel.length() / ball.radius).ceil() as u32)
+                .max(1)
+                .min(MAX_SWEEP_STEPS);
+            let step_travel = travel * (1.0 / substeps as f32);
+
+            let mut shattered = Vec::new();
+            for _ in 0..substeps {
+                ball.pos += step_travel;
+
+                // resolve against the single deepest contact this sub-step
+                // (pad or any overlapping shard), not every overlap in
+                // sequence -- a ball wedged between two edges reflected off
+                // both would have its velocity pushed along both normals
+                // additively, which can flip it right back into a surface.
+                let mut best: Option<(Contact, Option<usize>)> = None;
+                if let Some(contact) = ball.collides(&self.pad.poly) {
+                    best = Some((contact, None));
+                }
+                for (idx, s) in self.shards.iter().enumerate() {
+                    if s.hp < 1 || s.debris.is_some() {
+                        continue;
+                    }
+                    if let Some(contact) = ball.collides(&s.edges) {
+                        let deeper = best.is_none_or(|(b, _)| contact.penetration > b.penetration);
+                        if deeper {
+                            best = Some((contact, Some(idx)));
+                        }
+                    }
+                }
+
+                let Some((contact, shard_idx)) = best else {
+                    continue;
+                };
+                ball.speed = reflect(ball.speed, contact.normal);
+                ball.pos += contact.normal * contact.penetration;
+                if let Some(idx) = shard_idx {
+                    let s = &mut self.shards[idx];
+                    s.hp -= 1;
+                    if s.hp == 0 {
+                        shattered.push((idx, contact.point));
+                    }
+                }
+                break;
             }
-            if let Some(n) = self.ball.collides(&s.edges) {
-                eprintln!("hit shard {}", s.id);
-                self.ball.speed = reflect(self.ball.speed, n);
-                s.hp -= 1;
+            shards_destroyed += shattered.len() as u32;
+            for (idx, hit_point) in shattered.into_iter().rev() {
+                let s = self.shards.swap_remove(idx);
+                let fragments = s.shatter(hit_point, &mut self.rng, &mut self.next_shard_id);
+                self.shards.extend(fragments);
             }
+
+            if ball.pos.x <= ball.radius || ball.pos.x >= W as f32 - ball.radius {
+                ball.speed.x *= -1.0;
+            }
+            if ball.pos.y <= ball.radius {
+                ball.speed.y *= -1.0;
+            }
+            // below the paddle's plane and not caught by it (the pad check
+            // above only fires on x-overlap): the ball is lost instead of
+            // bouncing forever, so survival actually depends on the paddle.
         }
+        self.balls.retain(|b| b.pos.y - b.radius <= H as f32);
+
+        for i in 0..self.balls.len() {
+            for j in (i + 1)..self.balls.len() {
+                let (left, right) = self.balls.split_at_mut(j);
+                resolve_ball_collision(&mut left[i], &mut right[0], self.c_r);
+            }
+        }
+
+        for s in &mut self.shards {
+            if let Some(d) = &mut s.debris {
+                d.age += dt;
+                let drift = d.velocity * dt;
+                s.center += drift;
+                for e in &mut s.edges {
+                    *e += drift;
+                }
+            }
+        }
+        self.shards.retain(|s| match &s.debris {
+            Some(d) => d.age < DEBRIS_LIFETIME,
+            None => true,
+        });
+
+        shards_destroyed
     }
 }
 
@@ -202,73 +708,68 @@ fn reflect(v: Vector2, n: Vector2) -> Vector2 {
     }
 }
 
-fn main() {
-    let mut rng = rand::rng();
-    let mut points = Vec::with_capacity(100);
-    for _ in 0..points.capacity() {
-        points.push(Point {
-            x: rng.random_range(0.0..W as f64),
-            y: rng.random_range(0.0..(H / 3) as f64),
-        });
-    }
-    let voronoi = VoronoiBuilder::default()
-        .set_sites(points)
-        .set_bounding_box(BoundingBox::new(
-            Point {
-                x: (W / 2) as f64,
-                y: (H / 6) as f64,
-            },
-            W as f64,
-            (H / 3) as f64,
-        ))
-        .set_lloyd_relaxation_iterations(5)
-        .build()
-        .expect("building shards");
-    let shards: Vec<_> = voronoi
-        .iter_cells()
-        .enumerate()
-        .map(|(i, c)| Shard {
-            center: Vector2 {
-                x: c.site_position().x as f32,
-                y: c.site_position().y as f32,
-            },
-            edges: c
-                .iter_vertices()
-                .map(|v| Vector2::new(v.x as f32, v.y as f32))
-                .collect(),
-            hp: rng.random_range(1..5),
-            id: i,
+/// Spawns the starting set of balls just above the paddle's resting spot,
+/// each with a random upward-ish velocity (in units/second).
+fn spawn_balls(rng: &mut impl Rng) -> Vec<Ball> {
+    (0..3)
+        .map(|i| Ball {
+            pos: Vector2::new(
+                (W / 2 + PAD_W / 2 + i * BALL_R as i32 * 3) as f32,
+                (H - PAD_H - 1) as f32 - BALL_R,
+            ),
+            radius: BALL_R,
+            speed: Vector2::new(
+                rng.random_range(-600.0..600.0),
+                rng.random_range(-600.0..0.0),
+            ),
         })
-        .collect();
+        .collect()
+}
 
-    let (mut rl, thread) = raylib::init().size(W, H).title("Arkanoid").build();
-    let mut game = Game {
-        last_update: Instant::now(),
-        pad: Pad {
-            // vertexes shall go counter-clockwise:
-            //  3 +--------+ 2
-            //    |        |
-            //  0 +--------+ 1
-            poly: vec![
-                Vector2::new((W / 2) as f32, (H) as f32),
-                Vector2::new((W / 2 + PAD_W) as f32, (H) as f32),
-                Vector2::new((W / 2 + PAD_W) as f32, (H - PAD_H) as f32),
-                Vector2::new((W / 2) as f32, (H - PAD_H) as f32),
-            ],
-        },
-        shards,
-        ball: Ball {
-            pos: Vector2::new((W / 2 + PAD_W / 2) as f32, (H - PAD_H - 1) as f32 - BALL_R),
-            radius: BALL_R,
-            speed: Vector2::new(rng.random_range(-10.0..10.0), rng.random_range(-10.0..0.0)),
-        },
-    };
+fn main() {
+    let mut game = Game::new(StdRng::from_os_rng());
 
+    let (mut rl, thread) = raylib::init().size(W, H).title("Arkanoid").build();
     rl.set_target_fps(60);
     rl.gui_lock();
     rl.disable_cursor();
+
+    let mut ai_rng = StdRng::from_os_rng();
+    let mut population: Option<ai::Population> = None;
+    let mut brain: Option<ai::NN> = None;
+
     while !rl.window_should_close() {
-        game.update(&rl);
+        if rl.is_key_pressed(KeyboardKey::KEY_A) {
+            let mut pop = population
+                .take()
+                .unwrap_or_else(|| ai::Population::new(&mut ai_rng));
+            if !pop.is_training() {
+                // every genome in the generation plays a clone of the same
+                // fresh board, so the fitness comparison between them isn't
+                // decided by who drew the easier layout
+                let template = Game::new(StdRng::from_os_rng());
+                pop.start_generation(&template);
+            }
+            population = Some(pop);
+        }
+
+        if let Some(pop) = population.as_mut() {
+            // advances the current generation by a small step budget per
+            // frame instead of blocking the render loop until it's done
+            if let Some((best, fitness)) = pop.tick(&mut ai_rng) {
+                eprintln!(
+                    "ai: generation {} best fitness {:.1}",
+                    pop.generation, fitness
+                );
+                brain = Some(best);
+                game.reset_balls();
+            }
+        }
+
+        match &brain {
+            Some(nn) => game.update_ai(&rl, nn),
+            None => game.update(&rl),
+        }
         game.render(&mut rl, &thread);
     }
     rl.gui_unlock();